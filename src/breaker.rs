@@ -0,0 +1,81 @@
+//! Per-host circuit breaker guarding calls to a degraded OSS endpoint
+
+use std::{
+    collections::HashMap,
+    sync::{ Arc, Mutex },
+    time::{ Duration, Instant },
+};
+
+pub(crate) const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+pub(crate) const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+struct Breaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl Breaker {
+    fn new() -> Self {
+        Breaker { consecutive_failures: 0, opened_at: None }
+    }
+
+    fn should_try(&self, threshold: u32, cooldown: Duration) -> bool {
+        match self.opened_at {
+            None => true,
+            Some(opened_at) => self.consecutive_failures < threshold || opened_at.elapsed() >= cooldown,
+        }
+    }
+
+    fn succeed(&mut self) {
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+    }
+
+    fn fail(&mut self, threshold: u32) {
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= threshold {
+            self.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Per-host circuit breakers, keyed by endpoint host
+#[derive(Clone, Debug)]
+pub(crate) struct Breakers {
+    breakers: Arc<Mutex<HashMap<String, Breaker>>>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl Breakers {
+
+    pub(crate) fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Breakers {
+            breakers: Arc::new(Mutex::new(HashMap::new())),
+            failure_threshold,
+            cooldown,
+        }
+    }
+
+    /// Whether a request to `host` should be attempted, or short-circuited
+    /// because the breaker is open and its cooldown has not elapsed
+    pub(crate) fn should_try(&self, host: &str) -> bool {
+        let breakers = self.breakers.lock().unwrap();
+        breakers.get(host).map(|breaker| breaker.should_try(self.failure_threshold, self.cooldown)).unwrap_or(true)
+    }
+
+    pub(crate) fn succeed(&self, host: &str) {
+        self.breakers.lock().unwrap().entry(host.to_string()).or_insert_with(Breaker::new).succeed();
+    }
+
+    pub(crate) fn fail(&self, host: &str) {
+        self.breakers.lock().unwrap().entry(host.to_string()).or_insert_with(Breaker::new).fail(self.failure_threshold);
+    }
+}
+
+impl Default for Breakers {
+    fn default() -> Self {
+        Breakers::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOLDOWN)
+    }
+}
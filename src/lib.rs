@@ -6,7 +6,8 @@ use std::{
     fs::{ self, File },
     env,
     path::PathBuf,
-    io::{ Error, ErrorKind },
+    io::Error,
+    time::Duration,
 };
 use crypto::{
     mac::{ Mac, MacResult },
@@ -18,37 +19,137 @@ use rust_util::{ XResult, new_box_ioerror, util_time::get_current_secs };
 
 pub const OSS_VERB_GET: &str = "GET";
 pub const OSS_VERB_PUT: &str = "PUT";
+pub const OSS_VERB_POST: &str = "POST";
 pub const OSS_VERB_DELETE: &str = "DELETE";
 
+pub mod multipart;
+pub mod stream;
+pub mod post_policy;
+pub mod list;
+mod breaker;
+
+use breaker::Breakers;
+
+/// Extract the text content of the first `<tag>...</tag>` occurrence in an XML document
+///
+/// OSS responses are small, flat XML documents, so a full XML parser is
+/// overkill here — this mirrors the hand-rolled string building already
+/// used for request signing.
+pub(crate) fn extract_xml_tag(xml: &str, tag: &str) -> Option<String> {
+    let open_tag = format!("<{}>", tag);
+    let close_tag = format!("</{}>", tag);
+    let start = xml.find(&open_tag)? + open_tag.len();
+    let end = xml[start..].find(&close_tag)? + start;
+    Some(xml[start..end].to_string())
+}
+
+fn url_host(url: &str) -> XResult<String> {
+    reqwest::Url::parse(url)?.host_str().map(|host| host.to_string())
+        .ok_or_else(|| new_box_ioerror(&format!("Missing host in URL: {}", url)))
+}
+
 /// OSSClient - Alibaba Cloud OSS Client
-/// 
+///
 /// Reference URL: https://help.aliyun.com/document_detail/31952.html
-/// 
+///
 /// ```rust
-/// let oss_client = OSSClient::new("AK", "SK");
+/// use oss::OSSClient;
+/// let oss_client = OSSClient::new("endpoint", "AK", "SK");
 /// ```
 #[derive(Clone, Debug)]
 pub struct OSSClient {
     endpoint: String,
     access_key_id: String,
     access_key_secret: String,
+    security_token: Option<String>,
+    client: reqwest::Client,
+    breakers: Breakers,
+}
+
+/// A single request query string parameter, distinguishing whether it is a
+/// signable OSS sub-resource (folded into the `CanonicalizedResource` used
+/// for signing) or a plain, unsigned request parameter (appended to the URL
+/// only). See [`OSSClient::generate_signed_url_with_query`].
+pub(crate) enum QueryParam<'a> {
+    Signed(&'a str, Option<&'a str>),
+    Unsigned(&'a str, Option<&'a str>),
+}
+
+impl<'a> QueryParam<'a> {
+    pub(crate) fn signed(name: &'a str, value: Option<&'a str>) -> Self {
+        QueryParam::Signed(name, value)
+    }
+
+    pub(crate) fn unsigned(name: &'a str, value: Option<&'a str>) -> Self {
+        QueryParam::Unsigned(name, value)
+    }
+
+    fn as_signed(&self) -> Option<(&'a str, Option<&'a str>)> {
+        match self {
+            QueryParam::Signed(name, value) => Some((name, *value)),
+            QueryParam::Unsigned(..) => None,
+        }
+    }
+
+    fn as_tuple(&self) -> (&'a str, Option<&'a str>) {
+        match self {
+            QueryParam::Signed(name, value) | QueryParam::Unsigned(name, value) => (name, *value),
+        }
+    }
 }
 
 /// OSS Client implemention
 impl OSSClient {
 
     /// New OSSClient
-    /// 
-    /// Use access_key_id and access_key_secret to create a OSSClient
-    /// Consider support STS!
+    ///
+    /// Use access_key_id and access_key_secret to create a OSSClient.
+    /// Uses a default `reqwest::Client` with no custom timeouts; use
+    /// [`OSSClient::builder`] to configure timeouts and connection pooling.
     pub fn new(endpoint: &str, access_key_id: &str, access_key_secret: &str) -> OSSClient {
         OSSClient {
             endpoint: endpoint.into(),
             access_key_id: access_key_id.into(),
             access_key_secret: access_key_secret.into(),
+            security_token: None,
+            client: reqwest::Client::new(),
+            breakers: Breakers::default(),
         }
     }
 
+    /// New OSSClient with STS temporary credentials
+    ///
+    /// Use access_key_id, access_key_secret and security_token (returned
+    /// by an STS `AssumeRole` call) to create a OSSClient
+    pub fn new_with_token(endpoint: &str, access_key_id: &str, access_key_secret: &str, security_token: &str) -> OSSClient {
+        OSSClient {
+            endpoint: endpoint.into(),
+            access_key_id: access_key_id.into(),
+            access_key_secret: access_key_secret.into(),
+            security_token: Some(security_token.into()),
+            client: reqwest::Client::new(),
+            breakers: Breakers::default(),
+        }
+    }
+
+    /// Start building an OSSClient with a customized `reqwest::Client`
+    ///
+    /// Lets callers set request/connect timeouts and idle-pool size, e.g.:
+    /// ```rust
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use oss::OSSClient;
+    /// use std::time::Duration;
+    /// let oss_client = OSSClient::builder("endpoint", "AK", "SK")
+    ///     .with_timeout(Duration::from_secs(30))
+    ///     .with_connect_timeout(Duration::from_secs(5))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder(endpoint: &str, access_key_id: &str, access_key_secret: &str) -> OSSClientBuilder {
+        OSSClientBuilder::new(endpoint, access_key_id, access_key_secret)
+    }
+
     /// New OSSClient from JSON file
     pub fn from_file(f: &str) -> XResult<Self> {
         let f_path_buf = if f.starts_with("~/") {
@@ -62,51 +163,56 @@ impl OSSClient {
     }
 
     /// New OSSClient from JSON
-    /// 
+    ///
     /// JSON sample:
     /// ```json
     /// {
     ///     "endpoint": "",
     ///     "accessKeyId": "",
-    ///     "accessKeySecret": ""
+    ///     "accessKeySecret": "",
+    ///     "securityToken": ""
     /// }
     /// ```
+    /// `securityToken` is optional and only needed for STS temporary credentials.
     pub fn from_json(json: &str) -> XResult<Self> {
         let json_value = json::parse(json)?;
         if !json_value.is_object() {
-            return Err(Box::new(Error::new(ErrorKind::Other, format!("JSON format erorr: {}", json))));
+            return Err(Box::new(Error::other(format!("JSON format erorr: {}", json))));
         }
 
         let endpoint = json_value["endpoint"].as_str().unwrap_or_default();
         let access_key_id = json_value["accessKeyId"].as_str().unwrap_or_default();
         let access_key_secret = json_value["accessKeySecret"].as_str().unwrap_or_default();
+        let security_token = json_value["securityToken"].as_str().unwrap_or_default();
 
         if endpoint.is_empty() || access_key_id.is_empty() || access_key_secret.is_empty() {
-            return Err(Box::new(Error::new(ErrorKind::Other,"Endpoint, access_key_id or access_key_secret cannot be empty")));
+            return Err(Box::new(Error::other("Endpoint, access_key_id or access_key_secret cannot be empty")));
         }
 
-        Ok(Self::new(endpoint, access_key_id, access_key_secret))
+        Ok(if security_token.is_empty() {
+            Self::new(endpoint, access_key_id, access_key_secret)
+        } else {
+            Self::new_with_token(endpoint, access_key_id, access_key_secret, security_token)
+        })
     }
 
     /// Put file will read full file content to memory and send with HTTP protocol
     pub async fn put_file(&self, bucket_name: &str, key: &str, expire_in_seconds: u64, file: File) -> XResult<Response> {
         let put_url = self.generate_signed_put_url(bucket_name, key, expire_in_seconds);
-        let client = reqwest::Client::new();
         let mut v = vec![];
         let mut file = file;
         file.read_to_end(&mut v)?;
-        Ok(client.put(&put_url).body(v).send().await?)
+        self.execute_with_resilience(&put_url, true, || self.client.put(&put_url).body(v.clone())).await
     }
 
     pub async fn delete_file(&self, bucket_name: &str, key: &str) -> XResult<Response> {
         let delete_url = self.generate_signed_delete_url(bucket_name, key, 30_u64);
-        let client = reqwest::Client::new();
-        Ok(client.delete(&delete_url).send().await?)
+        self.execute_with_resilience(&delete_url, true, || self.client.delete(&delete_url)).await
     }
 
     pub async fn get_file_content(&self, bucket_name: &str, key: &str) -> XResult<Option<String>> {
         let get_url = self.generate_signed_get_url(bucket_name, key, 30_u64);
-        let response = reqwest::get(&get_url).await?;
+        let response = self.execute_with_resilience(&get_url, true, || self.client.get(&get_url)).await?;
         match response.status().as_u16() {
             404_u16 => Ok(None),
             200_u16 => Ok(Some(response.text().await?)),
@@ -116,7 +222,7 @@ impl OSSClient {
 
     pub async fn get_file_content_bytes(&self, bucket_name: &str, key: &str) -> XResult<Option<Vec<u8>>> {
         let get_url = self.generate_signed_get_url(bucket_name, key, 30_u64);
-        let response = reqwest::get(&get_url).await?;
+        let response = self.execute_with_resilience(&get_url, true, || self.client.get(&get_url)).await?;
         match response.status().as_u16() {
             404_u16 => Ok(None),
             200_u16 => Ok(Some(response.bytes().await?.as_ref().to_vec())),
@@ -130,8 +236,7 @@ impl OSSClient {
 
     pub async fn put_file_content_bytes(&self, bucket_name: &str, key: &str, content_bytes: Vec<u8>) -> XResult<Response> {
         let put_url = self.generate_signed_put_url(bucket_name, key, 30_u64);
-        let client = reqwest::Client::new();
-        Ok(client.put(&put_url).body(content_bytes).send().await?)
+        self.execute_with_resilience(&put_url, true, || self.client.put(&put_url).body(content_bytes.clone())).await
     }
 
     pub fn generate_signed_put_url(&self, bucket_name: &str, key: &str, expire_in_seconds: u64) -> String {
@@ -147,47 +252,253 @@ impl OSSClient {
     }
 
     pub fn generate_signed_url(&self, verb: &str, bucket_name: &str, key: &str, expire_in_seconds: u64, is_https: bool) -> String {
+        self.generate_signed_url_with_sub_resources(verb, bucket_name, key, expire_in_seconds, is_https, &[])
+    }
+
+    /// Generate a signed URL, additionally signing and appending OSS sub-resources
+    /// (e.g. `uploads`, `uploadId`, `partNumber` for multipart upload operations).
+    ///
+    /// `sub_resources` are appended to the request query string in the order
+    /// given, and to the canonicalized resource used for signing sorted
+    /// lexically by name (see `get_to_be_signed`). Only pass
+    /// genuinely signable OSS sub-resources here — plain request parameters
+    /// (e.g. `GetBucket`'s `prefix`/`marker`/`max-keys`) must go through
+    /// `generate_signed_url_with_query` instead, or signing will produce a
+    /// `CanonicalizedResource` the OSS server doesn't agree with.
+    pub(crate) fn generate_signed_url_with_sub_resources(
+        &self,
+        verb: &str,
+        bucket_name: &str,
+        key: &str,
+        expire_in_seconds: u64,
+        is_https: bool,
+        sub_resources: &[(&str, Option<&str>)],
+    ) -> String {
+        let query_params: Vec<QueryParam> = sub_resources.iter().map(|(name, value)| QueryParam::signed(name, *value)).collect();
+        self.generate_signed_url_with_query(verb, bucket_name, key, expire_in_seconds, is_https, &query_params)
+    }
+
+    /// Generate a signed URL like [`OSSClient::generate_signed_url_with_sub_resources`],
+    /// but accepting a mix of signed OSS sub-resources and plain, unsigned request
+    /// parameters via [`QueryParam`]. Unsigned entries are appended to the request
+    /// query string only — they are NOT part of the signed `CanonicalizedResource`.
+    /// Use [`QueryParam::Unsigned`] for parameters OSS does not treat as signable
+    /// sub-resources, such as `GetBucket`'s `prefix`/`delimiter`/`marker`/`max-keys`.
+    pub(crate) fn generate_signed_url_with_query(
+        &self,
+        verb: &str,
+        bucket_name: &str,
+        key: &str,
+        expire_in_seconds: u64,
+        is_https: bool,
+        query_params: &[QueryParam],
+    ) -> String {
         let mut signed_url = String::with_capacity(1024);
         signed_url.push_str(iff!(is_https, "https://", "http://"));
         signed_url.push_str(&format!("{}.{}/{}", bucket_name, self.endpoint, key));
-    
+
         let current_secs = get_current_secs();
         let expire_secs = current_secs + expire_in_seconds;
-    
+
         signed_url.push_str("?Expires=");
         signed_url.push_str(expire_secs.to_string().as_str());
         signed_url.push_str("&OSSAccessKeyId=");
         signed_url.push_str(&urlencoding::encode(&self.access_key_id));
         signed_url.push_str("&Signature=");
-    
-        let to_be_signed = get_to_be_signed(verb, expire_secs, bucket_name, key);
+
+        let sub_resources: Vec<(&str, Option<&str>)> = query_params.iter().filter_map(QueryParam::as_signed).collect();
+        let to_be_signed = get_to_be_signed(verb, expire_secs, bucket_name, key, self.security_token.as_deref(), &sub_resources);
         let signature = to_base64(calc_hmac_sha1(self.access_key_secret.as_bytes(), to_be_signed.as_bytes()));
         signed_url.push_str(&urlencoding::encode(signature.as_str()));
-    
+
+        if let Some(security_token) = &self.security_token {
+            signed_url.push_str("&security-token=");
+            signed_url.push_str(&urlencoding::encode(security_token));
+        }
+
+        for (name, value) in query_params.iter().map(QueryParam::as_tuple) {
+            signed_url.push('&');
+            signed_url.push_str(name);
+            if let Some(value) = value {
+                signed_url.push('=');
+                signed_url.push_str(&urlencoding::encode(value));
+            }
+        }
+
         signed_url
     }
+
+    pub(crate) fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    pub(crate) fn access_key_secret(&self) -> &str {
+        &self.access_key_secret
+    }
+
+    pub(crate) fn access_key_id(&self) -> &str {
+        &self.access_key_id
+    }
+
+    pub(crate) fn security_token(&self) -> Option<&str> {
+        self.security_token.as_deref()
+    }
+
+    pub(crate) fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Send a request built by `build_request`, guarded by the per-host circuit
+    /// breaker and, for idempotent verbs, retried with bounded exponential
+    /// backoff on `429` and `5xx` responses or transport errors.
+    pub(crate) async fn execute_with_resilience<F>(&self, url: &str, idempotent: bool, build_request: F) -> XResult<Response>
+    where F: Fn() -> reqwest::RequestBuilder {
+        const MAX_RETRIES: u32 = 3;
+        const BASE_BACKOFF: Duration = Duration::from_millis(200);
+
+        let host = url_host(url)?;
+        if !self.breakers.should_try(&host) {
+            return Err(new_box_ioerror(&format!("Circuit breaker open for host: {}", host)));
+        }
+
+        let mut attempt = 0_u32;
+        loop {
+            let outcome = build_request().send().await;
+            let is_retryable = match &outcome {
+                Ok(response) => response.status().as_u16() == 429 || response.status().is_server_error(),
+                Err(_) => true,
+            };
+
+            if !is_retryable {
+                self.breakers.succeed(&host);
+                return Ok(outcome?);
+            }
+
+            self.breakers.fail(&host);
+            if !idempotent || attempt >= MAX_RETRIES {
+                return Ok(outcome?);
+            }
+
+            tokio::time::sleep(BASE_BACKOFF * 2_u32.pow(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Builder for [`OSSClient`] that allows configuring the underlying `reqwest::Client`
+pub struct OSSClientBuilder {
+    endpoint: String,
+    access_key_id: String,
+    access_key_secret: String,
+    security_token: Option<String>,
+    client_builder: reqwest::ClientBuilder,
+    circuit_breaker_threshold: u32,
+    circuit_breaker_cooldown: Duration,
 }
 
-fn get_to_be_signed(verb: &str, expire_secs: u64, bucket_name: &str, key: &str) -> String {
+impl OSSClientBuilder {
+
+    fn new(endpoint: &str, access_key_id: &str, access_key_secret: &str) -> Self {
+        OSSClientBuilder {
+            endpoint: endpoint.into(),
+            access_key_id: access_key_id.into(),
+            access_key_secret: access_key_secret.into(),
+            security_token: None,
+            client_builder: reqwest::Client::builder(),
+            circuit_breaker_threshold: breaker::DEFAULT_FAILURE_THRESHOLD,
+            circuit_breaker_cooldown: breaker::DEFAULT_COOLDOWN,
+        }
+    }
+
+    /// Attach an STS security token to the client being built
+    pub fn with_security_token(mut self, security_token: &str) -> Self {
+        self.security_token = Some(security_token.into());
+        self
+    }
+
+    /// Set the overall request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client_builder = self.client_builder.timeout(timeout);
+        self
+    }
+
+    /// Set the TCP connect timeout
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.client_builder = self.client_builder.connect_timeout(timeout);
+        self
+    }
+
+    /// Set the maximum number of idle connections kept alive per host
+    pub fn with_pool_max_idle(mut self, max_idle: usize) -> Self {
+        self.client_builder = self.client_builder.pool_max_idle_per_host(max_idle);
+        self
+    }
+
+    /// Set the number of consecutive failures that opens a host's circuit breaker
+    pub fn with_circuit_breaker_threshold(mut self, threshold: u32) -> Self {
+        self.circuit_breaker_threshold = threshold;
+        self
+    }
+
+    /// Set how long a host's circuit breaker stays open before retrying
+    pub fn with_circuit_breaker_cooldown(mut self, cooldown: Duration) -> Self {
+        self.circuit_breaker_cooldown = cooldown;
+        self
+    }
+
+    /// Build the configured OSSClient
+    pub fn build(self) -> XResult<OSSClient> {
+        Ok(OSSClient {
+            endpoint: self.endpoint,
+            access_key_id: self.access_key_id,
+            access_key_secret: self.access_key_secret,
+            security_token: self.security_token,
+            client: self.client_builder.build()?,
+            breakers: Breakers::new(self.circuit_breaker_threshold, self.circuit_breaker_cooldown),
+        })
+    }
+}
+
+fn get_to_be_signed(verb: &str, expire_secs: u64, bucket_name: &str, key: &str, security_token: Option<&str>, sub_resources: &[(&str, Option<&str>)]) -> String {
     let mut to_be_signed = String::with_capacity(512);
     to_be_signed.push_str(verb);
-    to_be_signed.push_str("\n");
-    to_be_signed.push_str("\n");
-    to_be_signed.push_str("\n");
+    to_be_signed.push('\n');
+    to_be_signed.push('\n');
+    to_be_signed.push('\n');
     to_be_signed.push_str(expire_secs.to_string().as_str());
-    to_be_signed.push_str("\n");
-    to_be_signed.push_str("/");
+    to_be_signed.push('\n');
+    to_be_signed.push('/');
     to_be_signed.push_str(bucket_name);
-    to_be_signed.push_str("/");
+    to_be_signed.push('/');
     to_be_signed.push_str(key);
+
+    // The security token and any OSS sub-resources (e.g. multipart upload's
+    // `uploads`/`uploadId`/`partNumber`) are signed sub-resources: OSS requires
+    // them appended to the canonicalized resource, sorted lexically by name,
+    // not just present in the request query string.
+    let mut all_sub_resources: Vec<(&str, Option<&str>)> = sub_resources.to_vec();
+    if let Some(security_token) = security_token {
+        all_sub_resources.push(("security-token", Some(security_token)));
+    }
+    all_sub_resources.sort_by_key(|(name, _)| *name);
+
+    for (i, (name, value)) in all_sub_resources.iter().enumerate() {
+        to_be_signed.push_str(iff!(i == 0, "?", "&"));
+        to_be_signed.push_str(name);
+        if let Some(value) = value {
+            to_be_signed.push('=');
+            to_be_signed.push_str(value);
+        }
+    }
+
     to_be_signed
 }
 
-fn to_base64(mac_result: MacResult) -> String {
+pub(crate) fn to_base64(mac_result: MacResult) -> String {
     base64::encode(mac_result.code())
 }
 
-fn calc_hmac_sha1(key: &[u8], message: &[u8]) -> MacResult {
+pub(crate) fn calc_hmac_sha1(key: &[u8], message: &[u8]) -> MacResult {
     let mut hmac = Hmac::new(Sha1::new(), key);
     hmac.input(message);
     hmac.result()
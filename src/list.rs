@@ -0,0 +1,164 @@
+//! List objects (ListBucket) with prefix, delimiter, and pagination
+//!
+//! Reference URL: https://help.aliyun.com/document_detail/31965.html
+
+use std::collections::VecDeque;
+
+use futures::stream::{ self, Stream };
+use rust_util::{ XResult, new_box_ioerror };
+
+use crate::{ OSSClient, QueryParam, OSS_VERB_GET, extract_xml_tag };
+
+/// One object entry in a `ListBucketResult`
+#[derive(Clone, Debug)]
+pub struct ObjectSummary {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: String,
+    pub etag: String,
+}
+
+/// A single page of a `list_objects` call
+#[derive(Clone, Debug)]
+pub struct ListResult {
+    pub contents: Vec<ObjectSummary>,
+    pub common_prefixes: Vec<String>,
+    pub is_truncated: bool,
+    pub next_marker: Option<String>,
+}
+
+impl OSSClient {
+
+    /// List objects in `bucket_name`, optionally filtered by `prefix`, grouped by
+    /// `delimiter`, and paginated via `marker`/`max_keys` (OSS caps `max_keys` at 1000)
+    pub async fn list_objects(
+        &self,
+        bucket_name: &str,
+        prefix: Option<&str>,
+        delimiter: Option<&str>,
+        marker: Option<&str>,
+        max_keys: Option<u32>,
+    ) -> XResult<ListResult> {
+        // `prefix`/`delimiter`/`marker`/`max-keys` are plain GetBucket request
+        // parameters, not signable OSS sub-resources — they must not be folded
+        // into the CanonicalizedResource or the server rejects the signature.
+        let max_keys_str = max_keys.map(|max_keys| max_keys.to_string());
+        let mut query_params: Vec<QueryParam> = vec![];
+        if let Some(prefix) = prefix {
+            query_params.push(QueryParam::unsigned("prefix", Some(prefix)));
+        }
+        if let Some(delimiter) = delimiter {
+            query_params.push(QueryParam::unsigned("delimiter", Some(delimiter)));
+        }
+        if let Some(marker) = marker {
+            query_params.push(QueryParam::unsigned("marker", Some(marker)));
+        }
+        if let Some(max_keys_str) = &max_keys_str {
+            query_params.push(QueryParam::unsigned("max-keys", Some(max_keys_str.as_str())));
+        }
+
+        let url = self.generate_signed_url_with_query(OSS_VERB_GET, bucket_name, "", 30_u64, true, &query_params);
+        let response = self.execute_with_resilience(&url, true, || self.client().get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(new_box_ioerror(&format!("Error listing bucket: {}, returns: {:?}", bucket_name, response)));
+        }
+        parse_list_bucket_result(&response.text().await?)
+    }
+
+    /// Lazily iterate every object in `bucket_name` matching `prefix`/`delimiter`,
+    /// auto-following `next_marker` until `is_truncated` is false
+    pub fn list_objects_stream<'a>(&'a self, bucket_name: &'a str, prefix: Option<&'a str>, delimiter: Option<&'a str>) -> impl Stream<Item = XResult<ObjectSummary>> + 'a {
+        struct PageState<'a> {
+            client: &'a OSSClient,
+            bucket_name: &'a str,
+            prefix: Option<&'a str>,
+            delimiter: Option<&'a str>,
+            marker: Option<String>,
+            pending: VecDeque<ObjectSummary>,
+            done: bool,
+        }
+
+        let initial = PageState {
+            client: self,
+            bucket_name,
+            prefix,
+            delimiter,
+            marker: None,
+            pending: VecDeque::new(),
+            done: false,
+        };
+
+        stream::unfold(initial, |mut state| async move {
+            loop {
+                if let Some(item) = state.pending.pop_front() {
+                    return Some((Ok(item), state));
+                }
+                if state.done {
+                    return None;
+                }
+                match state.client.list_objects(state.bucket_name, state.prefix, state.delimiter, state.marker.as_deref(), Some(1000)).await {
+                    Ok(page) => {
+                        state.done = !page.is_truncated;
+                        if page.is_truncated {
+                            // OSS only returns <NextMarker> when a delimiter is given;
+                            // for a plain full-bucket listing, fall back to the last
+                            // key of this page as the next marker (standard OSS/S3
+                            // client behavior), to avoid re-fetching the same page
+                            // forever.
+                            let next_marker = page.next_marker.clone().or_else(|| page.contents.last().map(|item| item.key.clone()));
+                            match next_marker {
+                                Some(marker) => state.marker = Some(marker),
+                                None => {
+                                    state.done = true;
+                                    return Some((Err(new_box_ioerror("list_objects_stream: truncated page had no NextMarker and no contents to derive one from")), state));
+                                },
+                            }
+                        }
+                        state.pending.extend(page.contents);
+                    },
+                    Err(err) => {
+                        state.done = true;
+                        return Some((Err(err), state));
+                    },
+                }
+            }
+        })
+    }
+}
+
+fn parse_list_bucket_result(xml: &str) -> XResult<ListResult> {
+    let contents = extract_xml_blocks(xml, "Contents").into_iter().map(|block| ObjectSummary {
+        key: extract_xml_tag(block, "Key").unwrap_or_default(),
+        size: extract_xml_tag(block, "Size").and_then(|size| size.parse().ok()).unwrap_or_default(),
+        last_modified: extract_xml_tag(block, "LastModified").unwrap_or_default(),
+        etag: extract_xml_tag(block, "ETag").map(|etag| etag.trim_matches('"').to_string()).unwrap_or_default(),
+    }).collect();
+
+    let common_prefixes = extract_xml_blocks(xml, "CommonPrefixes").into_iter()
+        .filter_map(|block| extract_xml_tag(block, "Prefix"))
+        .collect();
+
+    let is_truncated = extract_xml_tag(xml, "IsTruncated").as_deref() == Some("true");
+    let next_marker = extract_xml_tag(xml, "NextMarker").filter(|marker| !marker.is_empty());
+
+    Ok(ListResult { contents, common_prefixes, is_truncated, next_marker })
+}
+
+/// Extract the inner content of every top-level `<tag>...</tag>` occurrence
+fn extract_xml_blocks<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open_tag = format!("<{}>", tag);
+    let close_tag = format!("</{}>", tag);
+    let mut blocks = vec![];
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open_tag) {
+        let after_open = &rest[start + open_tag.len()..];
+        match after_open.find(&close_tag) {
+            Some(end) => {
+                blocks.push(&after_open[..end]);
+                rest = &after_open[end + close_tag.len()..];
+            },
+            None => break,
+        }
+    }
+    blocks
+}
@@ -0,0 +1,153 @@
+//! Multipart upload support for large objects (OSS "Multipart Upload" API)
+//!
+//! Reference URL: https://help.aliyun.com/document_detail/31993.html
+
+use std::{ fs::File, io::Read };
+
+use futures::future::try_join_all;
+use reqwest::{ header::ETAG, Response };
+use rust_util::{ XResult, new_box_ioerror };
+
+use crate::{ OSSClient, OSS_VERB_PUT, OSS_VERB_POST, OSS_VERB_DELETE, extract_xml_tag };
+
+/// Minimum size of all but the last part, per the OSS multipart upload API
+pub const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+/// Maximum number of parts a single multipart upload may have
+pub const MAX_PART_NUMBER: u32 = 10_000;
+/// Default number of parts uploaded concurrently by `put_file_multipart`
+const DEFAULT_CONCURRENT_PARTS: usize = 4;
+
+/// One completed part of a multipart upload, as returned by `upload_part`
+#[derive(Clone, Debug)]
+pub struct UploadedPart {
+    pub part_number: u32,
+    pub etag: String,
+}
+
+impl OSSClient {
+
+    /// Initiate a multipart upload, returning the `uploadId` used by subsequent calls
+    pub async fn initiate_multipart_upload(&self, bucket_name: &str, key: &str) -> XResult<String> {
+        let url = self.generate_signed_url_with_sub_resources(OSS_VERB_POST, bucket_name, key, 30_u64, true, &[("uploads", None)]);
+        let response = self.execute_with_resilience(&url, false, || self.client().post(&url)).await?;
+        if !response.status().is_success() {
+            return Err(new_box_ioerror(&format!("Error initiating multipart upload: {}/{}, returns: {:?}", bucket_name, key, response)));
+        }
+        let body = response.text().await?;
+        extract_xml_tag(&body, "UploadId").ok_or_else(|| new_box_ioerror(&format!("Missing UploadId in response: {}", body)))
+    }
+
+    /// Upload a single part (1..=10000) of a multipart upload, returning its `ETag`.
+    /// Every part but the last must be at least `MIN_PART_SIZE` bytes.
+    pub async fn upload_part(&self, bucket_name: &str, key: &str, upload_id: &str, part_number: u32, content: Vec<u8>) -> XResult<UploadedPart> {
+        let part_number_str = part_number.to_string();
+        let url = self.generate_signed_url_with_sub_resources(
+            OSS_VERB_PUT, bucket_name, key, 30_u64, true,
+            &[("partNumber", Some(part_number_str.as_str())), ("uploadId", Some(upload_id))],
+        );
+        let response = self.execute_with_resilience(&url, true, || self.client().put(&url).body(content.clone())).await?;
+        if !response.status().is_success() {
+            return Err(new_box_ioerror(&format!("Error uploading part {} of: {}/{}, returns: {:?}", part_number, bucket_name, key, response)));
+        }
+        let etag = response.headers().get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim_matches('"').to_string())
+            .ok_or_else(|| new_box_ioerror(&format!("Missing ETag header uploading part {} of: {}/{}", part_number, bucket_name, key)))?;
+        Ok(UploadedPart { part_number, etag })
+    }
+
+    /// Complete a multipart upload, listing its parts in ascending part-number order
+    pub async fn complete_multipart_upload(&self, bucket_name: &str, key: &str, upload_id: &str, parts: &[UploadedPart]) -> XResult<Response> {
+        let mut sorted_parts = parts.to_vec();
+        sorted_parts.sort_by_key(|part| part.part_number);
+
+        let mut body = String::with_capacity(64 + sorted_parts.len() * 64);
+        body.push_str("<CompleteMultipartUpload>");
+        for part in &sorted_parts {
+            body.push_str("<Part>");
+            body.push_str(&format!("<PartNumber>{}</PartNumber>", part.part_number));
+            body.push_str(&format!("<ETag>\"{}\"</ETag>", part.etag));
+            body.push_str("</Part>");
+        }
+        body.push_str("</CompleteMultipartUpload>");
+
+        let url = self.generate_signed_url_with_sub_resources(OSS_VERB_POST, bucket_name, key, 30_u64, true, &[("uploadId", Some(upload_id))]);
+        let response = self.execute_with_resilience(&url, false, || self.client().post(&url).body(body.clone())).await?;
+        if !response.status().is_success() {
+            return Err(new_box_ioerror(&format!("Error completing multipart upload: {}/{}, returns: {:?}", bucket_name, key, response)));
+        }
+        Ok(response)
+    }
+
+    /// Abort a multipart upload, releasing any parts already uploaded
+    pub async fn abort_multipart_upload(&self, bucket_name: &str, key: &str, upload_id: &str) -> XResult<()> {
+        let url = self.generate_signed_url_with_sub_resources(OSS_VERB_DELETE, bucket_name, key, 30_u64, true, &[("uploadId", Some(upload_id))]);
+        let response = self.execute_with_resilience(&url, true, || self.client().delete(&url)).await?;
+        if !response.status().is_success() {
+            return Err(new_box_ioerror(&format!("Error aborting multipart upload: {}/{}, returns: {:?}", bucket_name, key, response)));
+        }
+        Ok(())
+    }
+
+    /// Upload a large file as a multipart upload, reading it in `part_size`-byte
+    /// chunks and uploading parts with bounded concurrency. At most
+    /// `DEFAULT_CONCURRENT_PARTS` chunks are read ahead of their upload and held
+    /// in memory at once, so peak memory stays bounded regardless of file size.
+    /// Aborts the upload on OSS if any part fails, so no storage is left dangling.
+    pub async fn put_file_multipart(&self, bucket_name: &str, key: &str, mut file: File, part_size: usize) -> XResult<Response> {
+        if part_size < MIN_PART_SIZE {
+            return Err(new_box_ioerror(&format!("part_size must be at least {} bytes", MIN_PART_SIZE)));
+        }
+
+        let mut upload_id: Option<String> = None;
+        let mut uploaded_parts = vec![];
+        let mut part_number = 0_u32;
+
+        loop {
+            let mut batch = vec![];
+            while batch.len() < DEFAULT_CONCURRENT_PARTS {
+                let mut buf = vec![0_u8; part_size];
+                let read = file.read(&mut buf)?;
+                if read == 0 {
+                    break;
+                }
+                buf.truncate(read);
+                part_number += 1;
+                if part_number > MAX_PART_NUMBER {
+                    if let Some(upload_id) = &upload_id {
+                        self.abort_multipart_upload(bucket_name, key, upload_id).await?;
+                    }
+                    return Err(new_box_ioerror(&format!("File needs more than the {} part OSS limit", MAX_PART_NUMBER)));
+                }
+                batch.push((part_number, buf));
+            }
+
+            if batch.is_empty() {
+                break;
+            }
+
+            // A multipart upload needs at least one part; OSS rejects
+            // `CompleteMultipartUpload` with an empty part list. Defer initiating
+            // one until the first non-empty batch is in hand, so a zero-byte
+            // file falls back to a plain PUT instead.
+            if upload_id.is_none() {
+                upload_id = Some(self.initiate_multipart_upload(bucket_name, key).await?);
+            }
+            let upload_id_ref = upload_id.as_ref().unwrap();
+
+            let uploads = batch.iter().map(|(part_number, content)| self.upload_part(bucket_name, key, upload_id_ref, *part_number, content.clone()));
+            match try_join_all(uploads).await {
+                Ok(batch_parts) => uploaded_parts.extend(batch_parts),
+                Err(err) => {
+                    self.abort_multipart_upload(bucket_name, key, upload_id_ref).await?;
+                    return Err(err);
+                },
+            }
+        }
+
+        match upload_id {
+            Some(upload_id) => self.complete_multipart_upload(bucket_name, key, &upload_id, &uploaded_parts).await,
+            None => self.put_file_content_bytes(bucket_name, key, vec![]).await,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Browser-facing signed POST policy generation for direct-to-OSS uploads
+//!
+//! Reference URL: https://help.aliyun.com/document_detail/31988.html
+
+use rust_util::util_time::get_current_secs;
+
+use crate::{ OSSClient, to_base64, calc_hmac_sha1 };
+
+/// The fields a `multipart/form-data` POST to `action_url` needs, alongside the
+/// file field itself, to upload directly to OSS with a short-lived policy.
+/// When `security_token` is present, it must be submitted as the
+/// `x-oss-security-token` form field, or OSS rejects the request.
+#[derive(Clone, Debug)]
+pub struct PostPolicy {
+    pub access_key_id: String,
+    pub policy: String,
+    pub signature: String,
+    pub key_prefix: String,
+    pub action_url: String,
+    pub security_token: Option<String>,
+}
+
+impl OSSClient {
+
+    /// Generate a signed POST policy letting a browser upload directly into
+    /// `bucket_name` under `key_prefix`, without proxying bytes through the server
+    pub fn generate_post_policy(&self, bucket_name: &str, key_prefix: &str, expire_in_seconds: u64, max_content_length: u64) -> PostPolicy {
+        let expiration = iso8601_utc(get_current_secs() + expire_in_seconds);
+
+        // Build with the `json` crate rather than interpolating into a string
+        // literal, so a `key_prefix` containing `"` or `\` can't produce
+        // malformed JSON or smuggle extra conditions into the signed policy.
+        let mut conditions = json::JsonValue::new_array();
+        conditions.push(json::object!{ "bucket" => bucket_name }).unwrap();
+        conditions.push(json::array![ "starts-with", "$key", key_prefix ]).unwrap();
+        conditions.push(json::array![ "content-length-range", 0, max_content_length ]).unwrap();
+        if let Some(security_token) = self.security_token() {
+            // A client built with a security token must echo it back in the
+            // conditions, and in the `x-oss-security-token` form field, or OSS
+            // rejects the request.
+            conditions.push(json::object!{ "x-oss-security-token" => security_token }).unwrap();
+        }
+
+        let policy_value = json::object!{
+            "expiration" => expiration,
+            "conditions" => conditions,
+        };
+        let policy = base64::encode(policy_value.dump().as_bytes());
+        let signature = to_base64(calc_hmac_sha1(self.access_key_secret().as_bytes(), policy.as_bytes()));
+
+        PostPolicy {
+            access_key_id: self.access_key_id().to_string(),
+            policy,
+            signature,
+            key_prefix: key_prefix.to_string(),
+            action_url: format!("https://{}.{}/", bucket_name, self.endpoint()),
+            security_token: self.security_token().map(str::to_string),
+        }
+    }
+}
+
+/// Format a unix timestamp as an ISO-8601 UTC instant (e.g. `2024-01-02T03:04:05Z`),
+/// hand-rolled to avoid pulling in a date/time dependency for a single format call.
+fn iso8601_utc(epoch_secs: u64) -> String {
+    let days = (epoch_secs / 86400) as i64;
+    let secs_of_day = epoch_secs % 86400;
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, secs_of_day / 3600, (secs_of_day % 3600) / 60, secs_of_day % 60)
+}
+
+/// Howard Hinnant's `civil_from_days`: days since the Unix epoch to a proleptic
+/// Gregorian (year, month, day). Day counts within a few hundred thousand
+/// days of either end of the `i64` range overflow the internal offsets.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}